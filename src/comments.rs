@@ -0,0 +1,321 @@
+//! Best-effort comment stripping used by `strip_comments`.
+//!
+//! Comments are recognized per language, skipping over string literals so
+//! that e.g. `"http://example.com"` is kept intact. Known limitations:
+//! - nested block comments (as in Rust or Haskell) end at the first closing
+//!   delimiter, and Lua's leveled `--[==[ ... ]==]` comments aren't recognized
+//! - raw strings containing their own quote character, heredocs and regex
+//!   literals (e.g. JavaScript's `/\/\//`) may be misread
+//! - a string literal spanning lines is only recognized for backtick quotes
+
+/// How comments and string literals look in a language
+pub(crate) struct CommentSyntax {
+    /// Line comment markers, e.g. `//` or `#`
+    line: &'static [&'static str],
+    /// Block comment delimiters, e.g. `/*` and `*/`
+    block: Option<(&'static str, &'static str)>,
+    /// Characters delimiting string literals
+    quotes: &'static [char],
+    /// Recognize `'x'` and `'\n'` char literals without treating other single
+    /// quotes (such as Rust lifetimes) as string delimiters
+    char_literals: bool,
+}
+
+const C_LIKE: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: Some(("/*", "*/")),
+    quotes: &['"'],
+    char_literals: true,
+};
+
+const SCRIPT_LIKE: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+    char_literals: false,
+};
+
+const HASH: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: None,
+    quotes: &['"', '\''],
+    char_literals: false,
+};
+
+const SQL: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    char_literals: false,
+};
+
+const LUA: CommentSyntax = CommentSyntax {
+    block: Some(("--[[", "]]")),
+    ..SQL
+};
+
+// Haskell 的 `'` 也用于标识符（如 `x'`），只按字符字面量识别
+const HASKELL: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: Some(("{-", "-}")),
+    quotes: &['"'],
+    char_literals: true,
+};
+
+/// Comment syntax for a fence language, `None` if comments aren't stripped for it
+pub(crate) fn comment_syntax(language: &str) -> Option<&'static CommentSyntax> {
+    match language {
+        "rust" | "c" | "cpp" | "csharp" | "java" | "kotlin" | "swift" | "scala" | "dart"
+        | "zig" | "protobuf" => Some(&C_LIKE),
+        "go" => Some(&CommentSyntax {
+            quotes: &['"', '`'],
+            ..C_LIKE
+        }),
+        "javascript" | "typescript" | "tsx" | "jsx" | "scss" | "less" => Some(&SCRIPT_LIKE),
+        "css" => Some(&CommentSyntax {
+            line: &[],
+            ..SCRIPT_LIKE
+        }),
+        "php" => Some(&CommentSyntax {
+            line: &["//", "#"],
+            ..SCRIPT_LIKE
+        }),
+        "python" | "ruby" | "bash" | "zsh" | "perl" | "r" | "toml" | "yaml" | "elixir"
+        | "graphql" | "powershell" => Some(&HASH),
+        "sql" => Some(&SQL),
+        "lua" => Some(&LUA),
+        "haskell" => Some(&HASKELL),
+        _ => None,
+    }
+}
+
+/// Remove comments from `content`
+///
+/// Lines left empty by a removed comment are dropped entirely and trailing
+/// whitespace before a removed comment is trimmed; other lines are unchanged.
+/// A leading `#!` shebang line is kept.
+pub(crate) fn strip_comments(content: &str, syntax: &CommentSyntax) -> String {
+    let mut stripper = Stripper::default();
+    let mut i = 0;
+
+    // 保留脚本开头的 shebang 行
+    if content.starts_with("#!") {
+        let end = content.find('\n').unwrap_or(content.len());
+        stripper.line.push_str(&content[..end]);
+        i = end;
+    }
+
+    while i < content.len() {
+        let rest = &content[i..];
+
+        if let Some((open, close)) = syntax.block.filter(|(open, _)| rest.starts_with(open)) {
+            let body = &rest[open.len()..];
+            let len = body
+                .find(close)
+                .map_or(rest.len(), |end| open.len() + end + close.len());
+            for _ in rest[..len].matches('\n') {
+                stripper.stripped = true;
+                stripper.finish_line(true);
+            }
+            stripper.stripped = true;
+            i += len;
+            continue;
+        }
+
+        let line_comment = syntax.line.iter().any(|marker| {
+            rest.starts_with(marker)
+                // `#` 只在行首或空白之后才算注释，避免误删 `$#`、`${#var}` 等写法
+                && (*marker != "#" || stripper.line.chars().last().is_none_or(char::is_whitespace))
+        });
+        if line_comment {
+            i += rest.find('\n').unwrap_or(rest.len());
+            stripper.stripped = true;
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+        if syntax.quotes.contains(&c) {
+            i += stripper.copy_string(rest, c);
+        } else if c == '\'' && syntax.char_literals {
+            i += stripper.copy_char_literal(rest);
+        } else if c == '\n' {
+            stripper.finish_line(true);
+            i += 1;
+        } else {
+            stripper.line.push(c);
+            i += c.len_utf8();
+        }
+    }
+
+    if !stripper.line.is_empty() || stripper.stripped {
+        stripper.finish_line(false);
+    }
+    stripper.output
+}
+
+#[derive(Default)]
+struct Stripper {
+    output: String,
+    /// The current line, without its newline
+    line: String,
+    /// Whether a comment was removed from the current line
+    stripped: bool,
+}
+
+impl Stripper {
+    fn finish_line(&mut self, newline: bool) {
+        let line = std::mem::take(&mut self.line);
+        if self.stripped {
+            let crlf = line.ends_with('\r');
+            let line = line.trim_end();
+            if !line.trim_start().is_empty() {
+                self.output.push_str(line);
+                if newline {
+                    self.output.push_str(if crlf { "\r\n" } else { "\n" });
+                }
+            }
+        } else {
+            self.output.push_str(&line);
+            if newline {
+                self.output.push('\n');
+            }
+        }
+        self.stripped = false;
+    }
+
+    /// Copy the string literal at the start of `rest`, returning its length
+    fn copy_string(&mut self, rest: &str, quote: char) -> usize {
+        let mut escaped = false;
+        for (index, c) in rest.char_indices() {
+            if c == '\n' {
+                // 只有反引号字符串可以跨行，其他未闭合的字符串在行尾结束
+                if quote != '`' {
+                    return index;
+                }
+                self.finish_line(true);
+                continue;
+            }
+            self.line.push(c);
+            if index == 0 {
+                continue;
+            }
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == quote {
+                return index + c.len_utf8();
+            }
+        }
+        rest.len()
+    }
+
+    /// Copy a `'x'` or `'\n'` char literal at the start of `rest`, or just the
+    /// quote if it doesn't start one; returns the number of bytes copied
+    fn copy_char_literal(&mut self, rest: &str) -> usize {
+        let mut chars = rest.char_indices().skip(1);
+        let literal_len = match chars.next() {
+            Some((_, '\\')) => {
+                // 跳过被转义的字符，再找结尾的引号，如 '\'' 或 '\u{1F600}'
+                chars.next();
+                chars
+                    .take_while(|(_, c)| *c != '\n')
+                    .find(|(_, c)| *c == '\'')
+                    .map(|(index, _)| index + 1)
+            }
+            Some((_, c)) if c != '\n' && c != '\'' => match chars.next() {
+                Some((index, '\'')) => Some(index + 1),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let len = literal_len.unwrap_or(1);
+        self.line.push_str(&rest[..len]);
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language::language_for_path;
+    use std::collections::HashMap;
+
+    fn strip(content: &str, language: &str) -> String {
+        strip_comments(content, comment_syntax(language).unwrap())
+    }
+
+    #[test]
+    fn test_strip_c_like_comments() {
+        let source = "// header\nfn main() { // entry\n    /* block */ let x = 1;\n    /* multi\n       line */\n    let s = \"// not a comment\";\n}\n";
+        assert_eq!(
+            strip(source, "rust"),
+            "fn main() {\n     let x = 1;\n    let s = \"// not a comment\";\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_char_literals_and_lifetimes() {
+        let source = "fn f<'a>(s: &'a str) -> char { '\"' } // quote\nlet c = '\\''; // escaped\n";
+        assert_eq!(
+            strip(source, "rust"),
+            "fn f<'a>(s: &'a str) -> char { '\"' }\nlet c = '\\'';\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_script_comments() {
+        let source = "const url = 'http://example.com'; // site\nconst t = `a\n// kept\n`;\n";
+        assert_eq!(
+            strip(source, "javascript"),
+            "const url = 'http://example.com';\nconst t = `a\n// kept\n`;\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_hash_comments() {
+        let source =
+            "#!/usr/bin/env python\n# comment\nx = \"#1\"  # trailing\nprint(len(\"$#\"))\n";
+        assert_eq!(
+            strip(source, "python"),
+            "#!/usr/bin/env python\nx = \"#1\"\nprint(len(\"$#\"))\n"
+        );
+        assert_eq!(
+            strip("echo ${#args[@]} # count\n", "bash"),
+            "echo ${#args[@]}\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_sql_comments() {
+        let source = "-- header\nSELECT '--kept' /* inline */ FROM t; -- trailing\n/* multi\n   line */\nWHERE 1;\n";
+        assert_eq!(strip(source, "sql"), "SELECT '--kept'  FROM t;\nWHERE 1;\n");
+    }
+
+    #[test]
+    fn test_strip_lua_comments() {
+        let source = "--[[ block\nstill comment ]]\nlocal s = \"--kept\" -- trailing\nprint(s)\n";
+        assert_eq!(strip(source, "lua"), "local s = \"--kept\"\nprint(s)\n");
+    }
+
+    #[test]
+    fn test_strip_haskell_comments() {
+        let source = "{- module\n   docs -}\nlet x' = 1 -- note\n    c = '-' -- char\n";
+        assert_eq!(strip(source, "haskell"), "let x' = 1\n    c = '-'\n");
+    }
+
+    #[test]
+    fn test_strip_by_path_language() {
+        // 通过 language_for_path 查找，确保两张表中的语言名一致
+        let defaults = HashMap::new();
+        let language = language_for_path("api/service.proto", &defaults).unwrap();
+        let source = "// service\nmessage Ping {} // empty\n";
+        assert_eq!(strip(source, language), "message Ping {}\n");
+    }
+
+    #[test]
+    fn test_unknown_language() {
+        assert!(comment_syntax("markdown").is_none());
+    }
+}
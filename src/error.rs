@@ -0,0 +1,43 @@
+//! Error type returned by the library API.
+
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Errors that can occur while configuring or running the packager
+#[derive(Debug, Error)]
+pub enum PackagerError {
+    /// A glob, regex or rule string couldn't be parsed; the message names it
+    #[error("{0}")]
+    InvalidPattern(String),
+    /// Reading a file or directory failed
+    #[error("I/O error on {}", .1.display())]
+    Io(#[source] io::Error, PathBuf),
+    /// An input directory doesn't exist
+    #[error("Input directory not found: {0}")]
+    InputNotFound(String),
+    /// A file is not valid UTF-8 text
+    #[error("File is not valid UTF-8: {}", .0.display())]
+    NotUtf8(PathBuf),
+    /// A configured path references an environment variable that isn't set
+    #[error("Environment variable {0} is not set (used in {1:?})")]
+    UnsetVariable(String, String),
+    /// `fail_on_empty` is set and no file was packaged
+    #[error("No files were packaged")]
+    NothingPackaged,
+    /// The configuration is invalid or needs a crate feature that is disabled
+    #[error("{0}")]
+    InvalidConfig(String),
+    /// Writing the packaged output failed
+    #[error("Failed to write output")]
+    Write(#[from] io::Error),
+}
+
+/// `Result` alias used by the library API
+pub type Result<T, E = PackagerError> = std::result::Result<T, E>;
+
+/// Map an [`io::Error`] to [`PackagerError::Io`] for `path`
+pub(crate) fn io_error(path: impl Into<PathBuf>) -> impl FnOnce(io::Error) -> PackagerError {
+    let path = path.into();
+    move |err| PackagerError::Io(err, path)
+}
@@ -0,0 +1,103 @@
+//! Expansion of `$VAR`, `${VAR}` and a leading `~` in configured paths.
+//!
+//! A `$` not followed by a variable name or `{` is kept literally, so globs
+//! and paths containing a lone `$` pass through unchanged.
+
+use crate::error::{PackagerError, Result};
+use std::env;
+
+/// Variable holding the home directory substituted for a leading `~`
+const HOME_VAR: &str = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+
+/// Expand environment variables and `~` in `value`
+///
+/// # Errors
+/// Returns [`PackagerError::UnsetVariable`] if a referenced variable is unset.
+pub(crate) fn expand_env(value: &str) -> Result<String> {
+    expand_with(value, |name| env::var(name).ok())
+}
+
+fn expand_with(value: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let variable = |name: &str| {
+        lookup(name)
+            .ok_or_else(|| PackagerError::UnsetVariable(name.to_string(), value.to_string()))
+    };
+
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    // 只展开开头的 `~` 或 `~/...`，`~user` 形式保持原样
+    if let Some(after) = rest.strip_prefix('~') {
+        if after.is_empty() || after.starts_with(['/', '\\']) {
+            expanded.push_str(&variable(HOME_VAR)?);
+            rest = after;
+        }
+    }
+
+    while let Some(dollar) = rest.find('$') {
+        expanded.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+
+        if let Some(braced) = after.strip_prefix('{') {
+            let end = braced.find('}').ok_or_else(|| {
+                PackagerError::InvalidConfig(format!("Unterminated ${{ in {}", value))
+            })?;
+            expanded.push_str(&variable(&braced[..end])?);
+            rest = &braced[end + 1..];
+        } else if after.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            let end = after
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(after.len());
+            expanded.push_str(&variable(&after[..end])?);
+            rest = &after[end..];
+        } else {
+            expanded.push('$');
+            rest = after;
+        }
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(value: &str) -> Result<String> {
+        expand_with(value, |name| match name {
+            "HOME" | "USERPROFILE" => Some("/home/me".to_string()),
+            "PROJECT" => Some("app".to_string()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_expand_variables() {
+        assert_eq!(expand("$HOME/dotfiles").unwrap(), "/home/me/dotfiles");
+        assert_eq!(expand("${PROJECT}_src/*.rs").unwrap(), "app_src/*.rs");
+        assert_eq!(expand("src/$PROJECT.rs").unwrap(), "src/app.rs");
+        assert_eq!(expand("~/notes").unwrap(), "/home/me/notes");
+        assert_eq!(expand("~").unwrap(), "/home/me");
+    }
+
+    #[test]
+    fn test_literal_text_is_kept() {
+        assert_eq!(expand("src/**/*.rs").unwrap(), "src/**/*.rs");
+        assert_eq!(expand("price$5 and $").unwrap(), "price$5 and $");
+        assert_eq!(expand("~user/file").unwrap(), "~user/file");
+        assert_eq!(expand("a/~/b").unwrap(), "a/~/b");
+    }
+
+    #[test]
+    fn test_unset_variable_is_an_error() {
+        assert!(matches!(
+            expand("$MISSING/src"),
+            Err(PackagerError::UnsetVariable(name, _)) if name == "MISSING"
+        ));
+        assert!(matches!(
+            expand("${PROJECT"),
+            Err(PackagerError::InvalidConfig(_))
+        ));
+    }
+}
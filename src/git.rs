@@ -0,0 +1,43 @@
+//! Commit and branch lookup for `include_git_info`, by running the `git` CLI.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// The commit checked out in a repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct GitInfo {
+    /// Full hash of `HEAD`
+    pub(crate) commit: String,
+    /// Current branch name, `None` for a detached `HEAD`
+    pub(crate) branch: Option<String>,
+}
+
+/// Look up the checked-out commit of the repository containing `dir`
+///
+/// Returns `None` if `dir` isn't inside a git repository, the repository has
+/// no commits yet, or `git` can't be run.
+pub(crate) fn git_info(dir: &Path) -> Option<GitInfo> {
+    let commit = run_git(dir, &["rev-parse", "HEAD"])?;
+    // 分离 HEAD 状态下 --abbrev-ref 输出 "HEAD"，此时不写分支名
+    let branch = run_git(dir, &["rev-parse", "--abbrev-ref", "HEAD"]).filter(|name| name != "HEAD");
+
+    Some(GitInfo { commit, branch })
+}
+
+/// Run a git command in `dir`, returning its trimmed stdout on success
+fn run_git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    Some(stdout.trim().to_string()).filter(|stdout| !stdout.is_empty())
+}
@@ -0,0 +1,179 @@
+//! Minimal `.gitignore` support used during directory traversal.
+//!
+//! Supports the commonly used parts of the gitignore format:
+//! - blank lines and lines starting with `#` are skipped
+//! - `!` negates a pattern, re-including previously ignored paths
+//! - a trailing `/` only matches directories
+//! - a leading or inner `/` anchors the pattern to the `.gitignore` directory,
+//!   otherwise the pattern matches a file name at any depth
+
+use crate::error::{io_error, Result};
+use glob::{MatchOptions, Pattern};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const GITIGNORE_FILE: &str = ".gitignore";
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: Pattern,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+/// Rules loaded from a single `.gitignore` file
+#[derive(Debug, Clone)]
+pub(crate) struct Gitignore {
+    base_dir: PathBuf,
+    rules: Vec<Rule>,
+}
+
+impl Gitignore {
+    /// Load `<dir>/.gitignore`, returning `None` if the directory has none
+    pub(crate) fn from_dir(dir: &Path) -> Result<Option<Self>> {
+        let file = dir.join(GITIGNORE_FILE);
+        if !file.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&file).map_err(io_error(&file))?;
+        Ok(Some(Self::parse(dir, &content)))
+    }
+
+    /// Parse gitignore content whose rules are relative to `base_dir`
+    pub(crate) fn parse(base_dir: &Path, content: &str) -> Self {
+        let rules = content.lines().filter_map(parse_line).collect();
+        Self {
+            base_dir: base_dir.to_path_buf(),
+            rules,
+        }
+    }
+
+    /// Returns `Some(true)` if ignored, `Some(false)` if re-included by a
+    /// negated rule, and `None` if no rule matches. The last matching rule wins.
+    fn matched(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.base_dir).ok()?;
+        let relative_str = relative.to_string_lossy().replace('\\', "/");
+        let file_name = relative.file_name()?.to_string_lossy();
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                if rule.dir_only && !is_dir {
+                    return false;
+                }
+                if rule.anchored {
+                    rule.pattern.matches_with(&relative_str, MATCH_OPTIONS)
+                } else {
+                    rule.pattern.matches_with(&file_name, MATCH_OPTIONS)
+                }
+            })
+            .map(|rule| !rule.negated)
+    }
+}
+
+/// Check a path against a stack of gitignores ordered from outermost to
+/// innermost; rules in deeper `.gitignore` files take precedence.
+pub(crate) fn is_ignored(stack: &[Gitignore], path: &Path, is_dir: bool) -> bool {
+    stack
+        .iter()
+        .rev()
+        .find_map(|gitignore| gitignore.matched(path, is_dir))
+        .unwrap_or(false)
+}
+
+fn parse_line(line: &str) -> Option<Rule> {
+    let line = line.trim_end();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (negated, line) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line.strip_prefix('\\').unwrap_or(line)),
+    };
+
+    let (dir_only, line) = match line.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+
+    let anchored = line.contains('/');
+    let line = line.strip_prefix('/').unwrap_or(line);
+    if line.is_empty() {
+        return None;
+    }
+
+    // 无法解析的规则直接跳过，与 git 的宽松处理保持一致
+    let pattern = Pattern::new(line).ok()?;
+    Some(Rule {
+        pattern,
+        negated,
+        dir_only,
+        anchored,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(content: &str, path: &str, is_dir: bool) -> bool {
+        let gitignore = Gitignore::parse(Path::new("/repo"), content);
+        is_ignored(&[gitignore], &Path::new("/repo").join(path), is_dir)
+    }
+
+    #[test]
+    fn test_unanchored_pattern_matches_any_depth() {
+        assert!(check("*.log", "debug.log", false));
+        assert!(check("*.log", "a/b/debug.log", false));
+        assert!(!check("*.log", "debug.txt", false));
+    }
+
+    #[test]
+    fn test_anchored_pattern() {
+        assert!(check("/build", "build", true));
+        assert!(!check("/build", "src/build", true));
+        assert!(check("doc/*.txt", "doc/notes.txt", false));
+        assert!(!check("doc/*.txt", "doc/server/notes.txt", false));
+        assert!(check("**/cache", "a/b/cache", true));
+    }
+
+    #[test]
+    fn test_directory_only_pattern() {
+        assert!(check("target/", "target", true));
+        assert!(!check("target/", "target", false));
+    }
+
+    #[test]
+    fn test_negation_last_rule_wins() {
+        let rules = "*.log\n!keep.log\n";
+        assert!(check(rules, "debug.log", false));
+        assert!(!check(rules, "keep.log", false));
+    }
+
+    #[test]
+    fn test_comments_and_blank_lines() {
+        let rules = "# comment\n\n\\#literal\n";
+        assert!(!check(rules, "comment", false));
+        assert!(check(rules, "#literal", false));
+    }
+
+    #[test]
+    fn test_nested_gitignore_overrides_parent() {
+        let root = Gitignore::parse(Path::new("/repo"), "*.log\n");
+        let nested = Gitignore::parse(Path::new("/repo/logs"), "!*.log\n");
+        let stack = [root, nested];
+
+        assert!(is_ignored(&stack, Path::new("/repo/a.log"), false));
+        assert!(!is_ignored(&stack, Path::new("/repo/logs/a.log"), false));
+    }
+}
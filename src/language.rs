@@ -0,0 +1,140 @@
+//! Mapping from file extensions to fence language hints.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Default extension (lowercase, without the dot) to language mapping
+const EXTENSION_LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "rust"),
+    ("py", "python"),
+    ("pyi", "python"),
+    ("ts", "typescript"),
+    ("tsx", "tsx"),
+    ("js", "javascript"),
+    ("mjs", "javascript"),
+    ("cjs", "javascript"),
+    ("jsx", "jsx"),
+    ("go", "go"),
+    ("java", "java"),
+    ("kt", "kotlin"),
+    ("kts", "kotlin"),
+    ("swift", "swift"),
+    ("c", "c"),
+    ("h", "c"),
+    ("cpp", "cpp"),
+    ("cc", "cpp"),
+    ("cxx", "cpp"),
+    ("hpp", "cpp"),
+    ("hh", "cpp"),
+    ("cs", "csharp"),
+    ("rb", "ruby"),
+    ("php", "php"),
+    ("sh", "bash"),
+    ("bash", "bash"),
+    ("zsh", "zsh"),
+    ("ps1", "powershell"),
+    ("lua", "lua"),
+    ("sql", "sql"),
+    ("html", "html"),
+    ("htm", "html"),
+    ("xml", "xml"),
+    ("css", "css"),
+    ("scss", "scss"),
+    ("less", "less"),
+    ("vue", "vue"),
+    ("svelte", "svelte"),
+    ("json", "json"),
+    ("yaml", "yaml"),
+    ("yml", "yaml"),
+    ("toml", "toml"),
+    ("ini", "ini"),
+    ("md", "markdown"),
+    ("markdown", "markdown"),
+    ("dart", "dart"),
+    ("scala", "scala"),
+    ("ex", "elixir"),
+    ("exs", "elixir"),
+    ("erl", "erlang"),
+    ("hs", "haskell"),
+    ("r", "r"),
+    ("pl", "perl"),
+    ("proto", "protobuf"),
+    ("graphql", "graphql"),
+    ("zig", "zig"),
+    ("nix", "nix"),
+    ("tf", "hcl"),
+];
+
+/// The built-in extension to fence language mapping
+///
+/// Keys are lowercase extensions without the leading dot, e.g. `"rs"` -> `"rust"`.
+pub fn default_language_map() -> HashMap<String, String> {
+    EXTENSION_LANGUAGES
+        .iter()
+        .map(|(ext, language)| (ext.to_string(), language.to_string()))
+        .collect()
+}
+
+/// Look up the fence language for a path based on its extension, consulting
+/// `overrides` before the built-in mapping
+pub(crate) fn language_for_path<'a>(
+    path: &str,
+    overrides: &'a HashMap<String, String>,
+) -> Option<&'a str> {
+    let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+
+    overrides.get(&extension).map(String::as_str).or_else(|| {
+        EXTENSION_LANGUAGES
+            .iter()
+            .find(|(ext, _)| *ext == extension)
+            .map(|(_, language)| *language)
+    })
+}
+
+/// Line comment delimiters `(prefix, suffix)` for a language, used to label
+/// fenced blocks without breaking syntax highlighting
+pub(crate) fn comment_delimiters(language: &str) -> (&'static str, &'static str) {
+    match language {
+        "python" | "ruby" | "bash" | "zsh" | "powershell" | "perl" | "r" | "toml" | "yaml"
+        | "ini" | "elixir" | "nix" | "hcl" | "graphql" => ("# ", ""),
+        "sql" | "lua" | "haskell" => ("-- ", ""),
+        "erlang" => ("% ", ""),
+        "html" | "xml" | "markdown" | "vue" | "svelte" => ("<!-- ", " -->"),
+        "css" => ("/* ", " */"),
+        _ => ("// ", ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_for_path() {
+        let defaults = HashMap::new();
+        assert_eq!(language_for_path("src/main.rs", &defaults), Some("rust"));
+        assert_eq!(language_for_path("app/Main.PY", &defaults), Some("python"));
+        assert_eq!(
+            language_for_path("web/index.ts", &defaults),
+            Some("typescript")
+        );
+        assert_eq!(language_for_path("data.unknown", &defaults), None);
+        assert_eq!(language_for_path("Makefile", &defaults), None);
+    }
+
+    #[test]
+    fn test_language_overrides() {
+        let overrides = HashMap::from([
+            ("acme".to_string(), "acmescript".to_string()),
+            ("h".to_string(), "cpp".to_string()),
+        ]);
+        assert_eq!(
+            language_for_path("lib/core.ACME", &overrides),
+            Some("acmescript")
+        );
+        assert_eq!(language_for_path("include/util.h", &overrides), Some("cpp"));
+        assert_eq!(language_for_path("src/main.rs", &overrides), Some("rust"));
+
+        assert_eq!(default_language_map()["rs"], "rust");
+    }
+}
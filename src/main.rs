@@ -2,12 +2,50 @@
 //!
 //! This binary provides a CLI for the code_packager library.
 
-use anyhow::Result;
-use clap::{Arg, Command};
-use code_packager::{merge_rule_config, package_code, parse_rule_string, PackagerConfig};
+use anyhow::{Context, Result};
+use clap::parser::ValueSource;
+use clap::{Arg, ArgMatches, Command};
+use code_packager::{
+    merge_rule_config, package_code, package_code_to_writer, parse_rfc3339, parse_rules,
+    PackagerConfig, Rule,
+};
+use std::fs;
+use std::io::{self, Write};
+use std::time::{Duration, SystemTime};
+
+/// Project config file loaded from the current directory when present
+#[cfg(feature = "toml")]
+const DEFAULT_CONFIG_FILE: &str = ".codepackager.toml";
+
+/// Commented config written by `code_packager init`
+#[cfg(feature = "toml")]
+const SAMPLE_CONFIG: &str = r#"# code_packager configuration, loaded automatically from the current directory.
+# Command-line arguments take precedence over the values in this file.
+
+# Directory whose files are packaged, searched recursively
+input_dir = "src"
+
+# File the packaged code is written to
+output_file = "src_code.txt"
+
+# Extra files or directories to include, e.g. from outside input_dir.
+# Glob patterns such as "docs/*.md" are supported; an entry starting with "!"
+# excludes matches from the entries before it, e.g. ["docs", "!docs/drafts"].
+extra_files = ["Cargo.toml", "README.md"]
+
+# Files and directories to leave out, as glob patterns matched against the
+# full path and the path relative to input_dir. A pattern matching a directory,
+# such as "target", ignores everything inside it. Prefix a pattern with
+# "regex:" to use a regular expression instead, e.g. "regex:\\.min\\.js$".
+ignore_patterns = ["target", "*.tmp", "*.lock"]
+
+# The same selection can be written as a single rule string on the command
+# line, where items starting with "!" are ignored:
+#   code_packager --rule "Cargo.toml + README.md + src + !target + !*.tmp"
+"#;
 
 fn main() -> Result<()> {
-    let matches = Command::new("code_packager")
+    let command = Command::new("code_packager")
         .version(env!("CARGO_PKG_VERSION"))
         .about(env!("CARGO_PKG_DESCRIPTION"))
         .arg(
@@ -15,7 +53,8 @@ fn main() -> Result<()> {
                 .short('i')
                 .long("input")
                 .value_name("DIR")
-                .help("Input directory path")
+                .action(clap::ArgAction::Append)
+                .help("Input directory path (can be repeated)")
                 .default_value("."),
         )
         .arg(
@@ -26,6 +65,12 @@ fn main() -> Result<()> {
                 .help("Output file path")
                 .default_value("src_code.txt"),
         )
+        .arg(
+            Arg::new("stdout")
+                .long("stdout")
+                .action(clap::ArgAction::SetTrue)
+                .help("Write the packaged content to stdout instead of the output file"),
+        )
         .arg(
             Arg::new("add")
                 .short('a')
@@ -41,12 +86,20 @@ fn main() -> Result<()> {
                 .action(clap::ArgAction::Append)
                 .help("Ignore files/directories matching pattern"),
         )
+        .arg(
+            Arg::new("ignore-from")
+                .long("ignore-from")
+                .value_name("FILE")
+                .action(clap::ArgAction::Append)
+                .help("Read ignore patterns from FILE, one per line (can be repeated)"),
+        )
         .arg(
             Arg::new("rule")
                 .long("rule")
                 .value_name("RULE_STRING")
+                .action(clap::ArgAction::Append)
                 .help(
-                "Rule string for including/excluding files (e.g., \"Cargo.toml + src + !target\")",
+                "Rule string for including/excluding files (e.g., \"Cargo.toml + src + !target\"); can be repeated, later rules take precedence",
             ),
         )
         .arg(
@@ -56,11 +109,148 @@ fn main() -> Result<()> {
                 .default_value("+")
                 .help("Separator used in rule string"),
         )
-        .get_matches();
+        .arg(
+            Arg::new("append")
+                .long("append")
+                .action(clap::ArgAction::SetTrue)
+                .help("Append to the output file instead of overwriting it"),
+        )
+        .arg(
+            Arg::new("relative-headers")
+                .long("relative-headers")
+                .action(clap::ArgAction::SetTrue)
+                .help("Show paths outside the input directory relative to the current directory"),
+        )
+        .arg(
+            Arg::new("no-packagerignore")
+                .long("no-packagerignore")
+                .action(clap::ArgAction::SetTrue)
+                .help("Don't read ignore patterns from .packagerignore files"),
+        )
+        .arg(
+            Arg::new("dry-run")
+                .long("dry-run")
+                .action(clap::ArgAction::SetTrue)
+                .help("List the files that would be packaged without writing output"),
+        )
+        .arg(
+            Arg::new("max-total-bytes")
+                .long("max-total-bytes")
+                .value_name("BYTES")
+                .value_parser(clap::value_parser!(u64))
+                .help("Stop adding files once the output would exceed BYTES"),
+        )
+        .arg(
+            Arg::new("fail-on-empty")
+                .long("fail-on-empty")
+                .action(clap::ArgAction::SetTrue)
+                .help("Exit with an error if no files were packaged"),
+        )
+        .arg(
+            Arg::new("files-from")
+                .long("files-from")
+                .value_name("FILE")
+                .help("Package exactly the files listed in FILE, one per line (\"-\" reads stdin)"),
+        )
+        .arg(
+            Arg::new("modified-since")
+                .long("modified-since")
+                .value_name("TIME")
+                .help("Only package files modified since TIME (RFC 3339, or a duration ago such as 2h or 3d)"),
+        )
+        .arg(
+            Arg::new("explain")
+                .long("explain")
+                .action(clap::ArgAction::SetTrue)
+                .help("Print how many paths each ignore pattern matched"),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .action(clap::ArgAction::Count)
+                .help("Log what is read and skipped to stderr (-v for debug, -vv for trace)"),
+        )
+        .arg(
+            Arg::new("show-tokens")
+                .long("show-tokens")
+                .action(clap::ArgAction::SetTrue)
+                .help("Print a rough estimate of the output's LLM token count"),
+        );
+
+    #[cfg(feature = "toml")]
+    let command = command
+        .arg(
+            Arg::new("config")
+                .short('c')
+                .long("config")
+                .value_name("FILE")
+                .help("Config file to load (defaults to .codepackager.toml if present)"),
+        )
+        .subcommand(
+            Command::new("init")
+                .about("Write a commented sample .codepackager.toml to the current directory")
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Overwrite an existing config file"),
+                ),
+        );
+
+    let matches = command.get_matches();
+
+    #[cfg(feature = "toml")]
+    if let Some(init) = matches.subcommand_matches("init") {
+        return write_sample_config(
+            std::path::Path::new(DEFAULT_CONFIG_FILE),
+            init.get_flag("force"),
+        );
+    }
+
+    // RUST_LOG 优先于 -v 指定的日志级别
+    let log_level = match matches.get_count("verbose") {
+        0 => log::LevelFilter::Warn,
+        1 => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Trace,
+    };
+    env_logger::Builder::new()
+        .filter_level(log_level)
+        .parse_default_env()
+        .init();
+
+    // Precedence: command-line arguments, then CODE_PACKAGER_* environment
+    // variables, then the config file, then the defaults of the arguments
+    let from_cli = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    let mut cli_input_dirs: Vec<String> = matches
+        .get_many("input")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let cli_input_dir = cli_input_dirs.remove(0);
+    let cli_output_file = matches.get_one::<String>("output").unwrap().to_string();
+    let base_config = match load_config_file(&matches)? {
+        Some(file_config) => file_config,
+        None => PackagerConfig {
+            input_dir: cli_input_dir.clone(),
+            input_dirs: cli_input_dirs.clone(),
+            output_file: cli_output_file.clone(),
+            ..PackagerConfig::default()
+        },
+    }
+    .with_env_overrides();
 
     // Get basic configuration
-    let input_dir = matches.get_one::<String>("input").unwrap().to_string();
-    let output_file = matches.get_one::<String>("output").unwrap().to_string();
+    let (input_dir, input_dirs) = if from_cli("input") {
+        (cli_input_dir, cli_input_dirs)
+    } else {
+        (base_config.input_dir, base_config.input_dirs)
+    };
+    let output_file = if from_cli("output") {
+        cli_output_file
+    } else {
+        base_config.output_file
+    };
     let cli_extra_files: Vec<String> = matches
         .get_many("add")
         .unwrap_or_default()
@@ -72,35 +262,289 @@ fn main() -> Result<()> {
         .cloned()
         .collect();
 
-    // Parse rule string if provided
-    let (rule_extra_files, rule_ignore_patterns) =
-        if let Some(rule_string) = matches.get_one::<String>("rule") {
-            let separator = matches.get_one::<String>("rule-separator").unwrap();
-            parse_rule_string(rule_string, separator)?
-        } else {
-            (Vec::new(), Vec::new())
-        };
+    // Parse rule strings if provided; items keep their order across all
+    // `--rule` arguments, so later items (and later rule strings) override earlier ones
+    let separator = matches.get_one::<String>("rule-separator").unwrap();
+    let rules = parse_rule_strings(
+        matches.get_many::<String>("rule").unwrap_or_default(),
+        separator,
+    )?;
 
-    // Merge rule configuration with CLI arguments
+    // Merge the config file (or environment) lists and CLI arguments, in that order
     let (extra_files, ignore_patterns) = merge_rule_config(
-        rule_extra_files,
-        rule_ignore_patterns,
+        base_config.extra_files,
+        base_config.ignore_patterns,
         cli_extra_files,
         cli_ignore_patterns,
     );
 
     let config = PackagerConfig {
         input_dir,
+        input_dirs,
         output_file,
         extra_files,
         ignore_patterns,
+        ignore_from: matches
+            .get_many("ignore-from")
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
+        rules,
+        append: matches.get_flag("append"),
+        respect_packagerignore: !matches.get_flag("no-packagerignore"),
+        explain: matches.get_flag("explain"),
+        relative_headers: matches.get_flag("relative-headers"),
+        dry_run: matches.get_flag("dry-run"),
+        fail_on_empty: matches.get_flag("fail-on-empty"),
+        max_total_bytes: matches.get_one::<u64>("max-total-bytes").copied(),
+        modified_since: matches
+            .get_one::<String>("modified-since")
+            .map(|value| parse_modified_since(value))
+            .transpose()?,
+        file_list: matches
+            .get_one::<String>("files-from")
+            .map(|source| read_file_list(source))
+            .transpose()?,
+        ..PackagerConfig::default()
+    };
+
+    let to_stdout = matches.get_flag("stdout") && !config.dry_run;
+    let report = if to_stdout {
+        let mut stdout = io::stdout().lock();
+        let report = package_code_to_writer(&config, &mut stdout)?;
+        stdout.flush().context("Failed to write to stdout")?;
+        report
+    } else {
+        package_code(&config)?
     };
 
-    package_code(&config)?;
+    // 模式命中统计写到 stderr，不影响 --stdout 的输出
+    for hits in &report.pattern_hits {
+        eprintln!("pattern {:?} matched {} paths", hits.pattern, hits.hits);
+    }
+
+    if config.dry_run {
+        for file in &report.files_matched {
+            println!("{}", file);
+        }
+        for file in &report.files_skipped {
+            println!("ignored: {}", file);
+        }
+        println!(
+            "Would package {} files ({} ignored)",
+            report.files_matched.len(),
+            report.files_skipped.len()
+        );
+        return Ok(());
+    }
 
-    println!(
-        "Source code successfully packaged to {}",
-        config.output_file
+    // 输出到 stdout 时，提示信息改写到 stderr，避免混入管道内容
+    let summary = format!(
+        "Packaged {} files, {}, {} lines",
+        report.files_written,
+        format_size(report.total_bytes),
+        report.lines_total
     );
+    let summary = if report.files_over_budget.is_empty() {
+        summary
+    } else {
+        format!(
+            "{} ({} files left out by --max-total-bytes)",
+            summary,
+            report.files_over_budget.len()
+        )
+    };
+    if to_stdout {
+        eprintln!("{}", summary);
+    } else {
+        println!(
+            "Source code successfully packaged to {}",
+            config.output_file
+        );
+        println!("{}", summary);
+    }
+    if matches.get_flag("show-tokens") {
+        let tokens = format!("Estimated tokens: ~{}", report.estimated_tokens);
+        if to_stdout {
+            eprintln!("{}", tokens);
+        } else {
+            println!("{}", tokens);
+        }
+    }
     Ok(())
 }
+
+/// Load the config file given by `--config`, or the default one if it exists
+#[cfg(feature = "toml")]
+fn load_config_file(matches: &ArgMatches) -> Result<Option<PackagerConfig>> {
+    match matches.get_one::<String>("config") {
+        Some(path) => Ok(Some(PackagerConfig::from_toml_file(path)?)),
+        None if std::path::Path::new(DEFAULT_CONFIG_FILE).is_file() => {
+            Ok(Some(PackagerConfig::from_toml_file(DEFAULT_CONFIG_FILE)?))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Write [`SAMPLE_CONFIG`] to `path`, normally [`DEFAULT_CONFIG_FILE`]
+#[cfg(feature = "toml")]
+fn write_sample_config(path: &std::path::Path, force: bool) -> Result<()> {
+    if !force && path.exists() {
+        anyhow::bail!(
+            "{} already exists (use --force to overwrite it)",
+            path.display()
+        );
+    }
+    fs::write(path, SAMPLE_CONFIG).context(format!("Failed to write {}", path.display()))?;
+
+    println!("Wrote sample config to {}", path.display());
+    Ok(())
+}
+
+#[cfg(not(feature = "toml"))]
+fn load_config_file(_matches: &ArgMatches) -> Result<Option<PackagerConfig>> {
+    Ok(None)
+}
+
+/// Parse several rule strings into one ordered rule list
+///
+/// The rules of each string follow those of the previous one, so for any path
+/// the last matching item of the last matching string wins.
+fn parse_rule_strings<'a>(
+    rule_strings: impl IntoIterator<Item = &'a String>,
+    separator: &str,
+) -> Result<Vec<Rule>> {
+    let mut rules = Vec::new();
+    for rule_string in rule_strings {
+        rules.extend(parse_rules(rule_string, separator)?);
+    }
+    Ok(rules)
+}
+
+/// Read newline-separated file paths from `source`, or from stdin for `-`
+fn read_file_list(source: &str) -> Result<Vec<String>> {
+    let content = if source == "-" {
+        io::read_to_string(io::stdin()).context("Failed to read file list from stdin")?
+    } else {
+        fs::read_to_string(source).context(format!("Failed to read file list: {}", source))?
+    };
+
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+/// Parse a `--modified-since` value: an RFC 3339 timestamp such as
+/// `2024-05-01T12:00:00Z`, or a duration before now such as `30m`, `2h` or `3d`
+fn parse_modified_since(value: &str) -> Result<SystemTime> {
+    if let Some(ago) = parse_duration(value) {
+        return SystemTime::now()
+            .checked_sub(ago)
+            .context(format!("Duration is too large: {}", value));
+    }
+
+    parse_rfc3339(value).context(format!(
+        "Invalid --modified-since value (expected RFC 3339 or a duration like 2h): {}",
+        value
+    ))
+}
+
+/// Parse a duration such as `90s`, `30m`, `2h`, `3d` or `1w`
+fn parse_duration(value: &str) -> Option<Duration> {
+    let unit_start = value.find(|c: char| !c.is_ascii_digit())?;
+    let amount: u64 = value[..unit_start].parse().ok()?;
+    let unit_seconds = match &value[unit_start..] {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return None,
+    };
+    Some(Duration::from_secs(amount.checked_mul(unit_seconds)?))
+}
+
+/// Format a byte count as a short human-readable size (e.g. "1.2MB")
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{}B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1}{}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30m"), Some(Duration::from_secs(1_800)));
+        assert_eq!(parse_duration("2h"), Some(Duration::from_secs(7_200)));
+        assert_eq!(parse_duration("3d"), Some(Duration::from_secs(259_200)));
+        assert_eq!(parse_duration("1w"), Some(Duration::from_secs(604_800)));
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_invalid() {
+        assert_eq!(parse_duration("5y"), None);
+        assert_eq!(parse_duration("5"), None);
+        assert_eq!(parse_duration("h"), None);
+        assert_eq!(parse_duration("2hm"), None);
+        assert_eq!(parse_duration("-2h"), None);
+        // 数量乘以单位秒数溢出
+        assert_eq!(
+            parse_duration(&format!("{}w", u64::MAX / 604_800 + 1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_modified_since() {
+        assert_eq!(
+            parse_modified_since("1970-01-01T08:00:10+08:00").unwrap(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(10)
+        );
+        assert!(parse_modified_since("2h").unwrap() < SystemTime::now());
+        assert!(parse_modified_since("2024-02-31T00:00:00Z").is_err());
+        assert!(parse_modified_since(&format!("{}s", u64::MAX)).is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_sample_config_is_valid() {
+        let config = PackagerConfig::from_toml_str(SAMPLE_CONFIG).unwrap();
+        assert_eq!(config.input_dir, "src");
+        assert_eq!(config.extra_files, vec!["Cargo.toml", "README.md"]);
+        assert_eq!(config.ignore_patterns, vec!["target", "*.tmp", "*.lock"]);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_init_refuses_to_overwrite() -> Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let path = temp_dir.path().join(DEFAULT_CONFIG_FILE);
+        fs::write(&path, "input_dir = \"lib\"\n")?;
+
+        assert!(write_sample_config(&path, false).is_err());
+        assert_eq!(fs::read_to_string(&path)?, "input_dir = \"lib\"\n");
+
+        write_sample_config(&path, true)?;
+        assert_eq!(fs::read_to_string(&path)?, SAMPLE_CONFIG);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+//! RFC 3339 timestamps in UTC, used by `include_mtime`, the footer summary and
+//! the CLI's `--modified-since`.
+//!
+//! Calendar arithmetic follows Howard Hinnant's `days_from_civil` and
+//! `civil_from_days` algorithms for the proleptic Gregorian calendar.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Format a time as an ISO-8601 UTC timestamp such as `2024-05-01T12:34:56Z`
+///
+/// Returns `None` for times before the Unix epoch.
+pub(crate) fn format_timestamp(time: SystemTime) -> Option<String> {
+    let seconds = time.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    let (days, seconds_of_day) = (seconds / 86_400, seconds % 86_400);
+    let (year, month, day) = civil_from_days(i64::try_from(days).ok()?);
+
+    Some(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    ))
+}
+
+/// Parse an RFC 3339 timestamp such as `2024-05-01T12:00:00Z` or
+/// `2024-05-01T20:00:00.5+08:00`
+///
+/// Fractional seconds are ignored. Returns `None` if `value` isn't a valid
+/// timestamp, including impossible dates like `2024-02-31`, or if it lies
+/// before the Unix epoch.
+///
+/// # Examples
+/// ```
+/// use code_packager::parse_rfc3339;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// assert_eq!(
+///     parse_rfc3339("1970-01-02T08:00:00+08:00"),
+///     Some(UNIX_EPOCH + Duration::from_secs(86_400))
+/// );
+/// assert_eq!(parse_rfc3339("2024-02-31T00:00:00Z"), None);
+/// ```
+pub fn parse_rfc3339(value: &str) -> Option<SystemTime> {
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let digits = value.get(range)?;
+        digits
+            .bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| digits.parse().ok())?
+    };
+    let separators = [(4, b'-'), (7, b'-'), (13, b':'), (16, b':')];
+    let bytes = value.as_bytes();
+    if bytes.len() < 20
+        || !matches!(bytes[10], b'T' | b't' | b' ')
+        || separators.iter().any(|&(index, sep)| bytes[index] != sep)
+    {
+        return None;
+    }
+
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+    if !(1..=12).contains(&month)
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    // 跳过小数秒，再解析时区偏移
+    let mut rest = &value[19..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(fraction.len());
+        if digits == 0 {
+            return None;
+        }
+        rest = &fraction[digits..];
+    }
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes().first()? {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let (hours, minutes) = rest.get(1..)?.split_once(':')?;
+            let field = |digits: &str, max: i64| {
+                let valid = digits.len() == 2 && digits.bytes().all(|b| b.is_ascii_digit());
+                valid
+                    .then(|| digits.parse::<i64>().ok())?
+                    .filter(|&value| value <= max)
+            };
+            sign * (field(hours, 23)? * 3_600 + field(minutes, 59)? * 60)
+        }
+    };
+
+    let days = days_from_civil(year, month, day);
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second - offset;
+    let seconds = u64::try_from(seconds).ok()?;
+    UNIX_EPOCH.checked_add(Duration::from_secs(seconds))
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since the Unix epoch of a date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// `(year, month, day)` of a day counted from the Unix epoch
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: u64) -> Option<SystemTime> {
+        Some(UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    #[test]
+    fn test_parse_utc() {
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:00Z"), at(0));
+        assert_eq!(parse_rfc3339("2000-02-29t12:34:56z"), at(951_827_696));
+        assert_eq!(parse_rfc3339("2000-02-29 12:34:56Z"), at(951_827_696));
+    }
+
+    #[test]
+    fn test_parse_offsets_and_fractions() {
+        assert_eq!(parse_rfc3339("1970-01-01T08:00:00+08:00"), at(0));
+        assert_eq!(parse_rfc3339("1969-12-31T18:30:00-05:30"), at(0));
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:01.999Z"), at(1));
+        assert_eq!(parse_rfc3339("1970-01-01T08:00:01.5+08:00"), at(1));
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:01.Z"), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        assert_eq!(parse_rfc3339("1970/01/01T00:00:00Z"), None);
+        assert_eq!(parse_rfc3339("1970-01-01X00:00:00Z"), None);
+        assert_eq!(parse_rfc3339("1970-01-01T00-00-00Z"), None);
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:00"), None);
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:00+0800"), None);
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:00+08:+0"), None);
+        assert_eq!(parse_rfc3339("197a-01-01T00:00:00Z"), None);
+        // 纪元之前的时间无法表示
+        assert_eq!(parse_rfc3339("1969-12-31T23:59:59Z"), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_out_of_range() {
+        assert_eq!(parse_rfc3339("2024-13-01T00:00:00Z"), None);
+        assert_eq!(parse_rfc3339("2024-00-01T00:00:00Z"), None);
+        assert_eq!(parse_rfc3339("2024-02-31T00:00:00Z"), None);
+        assert_eq!(parse_rfc3339("2023-02-29T00:00:00Z"), None);
+        assert_eq!(parse_rfc3339("2100-02-29T00:00:00Z"), None);
+        assert_eq!(parse_rfc3339("2024-04-31T00:00:00Z"), None);
+        assert_eq!(parse_rfc3339("2024-01-01T24:00:00Z"), None);
+        assert_eq!(parse_rfc3339("2024-01-01T00:60:00Z"), None);
+        assert_eq!(parse_rfc3339("2024-01-01T00:00:61Z"), None);
+        assert_eq!(parse_rfc3339("2024-01-01T00:00:00+24:00"), None);
+        assert!(parse_rfc3339("2024-02-29T00:00:00Z").is_some());
+        assert!(parse_rfc3339("2000-02-29T00:00:00Z").is_some());
+    }
+
+    #[test]
+    fn test_format_round_trips() {
+        for seconds in [0, 951_827_696, 1_709_164_800, 4_102_444_799] {
+            let time = UNIX_EPOCH + Duration::from_secs(seconds);
+            assert_eq!(parse_rfc3339(&format_timestamp(time).unwrap()), Some(time));
+        }
+    }
+}
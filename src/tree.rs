@@ -0,0 +1,40 @@
+//! Rendering of a `tree`-style listing for the packaged files.
+
+use std::collections::BTreeMap;
+
+#[derive(Default)]
+struct Node {
+    children: BTreeMap<String, Node>,
+}
+
+/// Render paths as an ASCII tree rooted at `.`, like the `tree` command
+pub(crate) fn render_tree<'a>(paths: impl IntoIterator<Item = &'a str>) -> String {
+    let mut root = Node::default();
+    for path in paths {
+        let mut node = &mut root;
+        for component in path
+            .split(['/', '\\'])
+            .filter(|component| !component.is_empty() && *component != ".")
+        {
+            node = node.children.entry(component.to_string()).or_default();
+        }
+    }
+
+    let mut rendered = String::from(".\n");
+    render_children(&root, "", &mut rendered);
+    rendered
+}
+
+fn render_children(node: &Node, prefix: &str, rendered: &mut String) {
+    let count = node.children.len();
+    for (index, (name, child)) in node.children.iter().enumerate() {
+        let is_last = index + 1 == count;
+        rendered.push_str(prefix);
+        rendered.push_str(if is_last { "└── " } else { "├── " });
+        rendered.push_str(name);
+        rendered.push('\n');
+
+        let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+        render_children(child, &child_prefix, rendered);
+    }
+}